@@ -0,0 +1,346 @@
+use std::collections::HashMap;
+use std::fs;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::debug_log;
+
+/// What to do with the non-kept members of a duplicate group.
+#[derive(Debug, Clone, Copy)]
+pub enum DedupAction {
+    Report,
+    Delete,
+    Hardlink,
+    Symlink,
+}
+
+/// Which member of a duplicate group to keep when applying a `DedupAction`.
+#[derive(Debug, Clone, Copy)]
+pub enum KeepPolicy {
+    First,
+    Oldest,
+    Newest,
+    ShortestPath,
+}
+
+/// The outcome recorded for a single file after its group has been
+/// resolved, surfaced in the txt/csv/db writers alongside its group.
+#[derive(Debug, Clone)]
+pub struct FileOutcome {
+    pub file: String,
+    pub action: String,
+}
+
+/// Picks a keeper for every group per `keep`, then applies `action` to each
+/// other member (or, under `dry_run`, just labels what would happen without
+/// touching the filesystem).
+pub async fn resolve_groups(
+    groups: HashMap<String, Vec<String>>,
+    action: DedupAction,
+    keep: KeepPolicy,
+    dry_run: bool,
+    verbose: bool,
+) -> HashMap<String, Vec<FileOutcome>> {
+    let mut resolved = HashMap::with_capacity(groups.len());
+
+    for (hash, files) in groups {
+        let keeper = pick_keeper(&files, keep).await;
+        debug_log(verbose, &format!("Group {}: keeping {}", hash, keeper));
+
+        let mut outcomes = Vec::with_capacity(files.len());
+        for file in files {
+            if file == keeper {
+                outcomes.push(FileOutcome { file, action: "kept".to_string() });
+                continue;
+            }
+            let label = apply_action(&file, &keeper, action, dry_run, verbose);
+            outcomes.push(FileOutcome { file, action: label });
+        }
+
+        resolved.insert(hash, outcomes);
+    }
+
+    resolved
+}
+
+async fn pick_keeper(files: &[String], keep: KeepPolicy) -> String {
+    match keep {
+        // `files`' order comes from concurrent JoinSet/HashMap completion,
+        // which is nondeterministic, so "first" picks the lexicographically
+        // smallest path rather than files[0].
+        KeepPolicy::First => files.iter().min().cloned().unwrap_or_else(|| files[0].clone()),
+        // `min_by_key` keeps the first-seen element on a tie, which is just
+        // as order-dependent as the `First` policy's raw `files[0]` would
+        // be, so break ties on path the same way.
+        KeepPolicy::ShortestPath => files
+            .iter()
+            .min_by_key(|f| (f.len(), f.as_str()))
+            .cloned()
+            .unwrap_or_else(|| files[0].clone()),
+        KeepPolicy::Oldest | KeepPolicy::Newest => {
+            let mut best = files[0].clone();
+            let mut best_mtime = file_mtime(&best).await;
+
+            for file in &files[1..] {
+                let mtime = file_mtime(file).await;
+                let replace = match keep {
+                    KeepPolicy::Oldest => {
+                        mtime < best_mtime || (mtime == best_mtime && file < &best)
+                    }
+                    KeepPolicy::Newest => {
+                        mtime > best_mtime || (mtime == best_mtime && file < &best)
+                    }
+                    _ => unreachable!(),
+                };
+                if replace {
+                    best = file.clone();
+                    best_mtime = mtime;
+                }
+            }
+
+            best
+        }
+    }
+}
+
+async fn file_mtime(file: &str) -> SystemTime {
+    tokio::fs::metadata(file)
+        .await
+        .and_then(|m| m.modified())
+        .unwrap_or(UNIX_EPOCH)
+}
+
+fn apply_action(file: &str, keeper: &str, action: DedupAction, dry_run: bool, verbose: bool) -> String {
+    match action {
+        DedupAction::Report => "duplicate".to_string(),
+        DedupAction::Delete => {
+            if dry_run {
+                debug_log(verbose, &format!("Dry run: would delete {}", file));
+                return "would-delete".to_string();
+            }
+            match fs::remove_file(file) {
+                Ok(()) => "deleted".to_string(),
+                Err(e) => {
+                    eprintln!("Warning: Failed to delete '{}': {}", file, e);
+                    "error".to_string()
+                }
+            }
+        }
+        DedupAction::Hardlink => {
+            if dry_run {
+                debug_log(verbose, &format!("Dry run: would hardlink {} -> {}", file, keeper));
+                return "would-hardlink".to_string();
+            }
+            match relink(file, |tmp| fs::hard_link(keeper, tmp)) {
+                Ok(()) => "hardlinked".to_string(),
+                Err(e) => {
+                    eprintln!("Warning: Failed to hardlink '{}' to '{}': {}", file, keeper, e);
+                    "error".to_string()
+                }
+            }
+        }
+        DedupAction::Symlink => {
+            if dry_run {
+                debug_log(verbose, &format!("Dry run: would symlink {} -> {}", file, keeper));
+                return "would-symlink".to_string();
+            }
+            // A symlink's target is resolved relative to the link's own
+            // directory, not the CWD/walk root, so a relative `keeper` would
+            // produce a dangling link unless `file` and `keeper` happen to
+            // share a parent. Canonicalize to an absolute target instead.
+            let target = match fs::canonicalize(keeper) {
+                Ok(path) => path,
+                Err(e) => {
+                    eprintln!("Warning: Failed to resolve symlink target '{}': {}", keeper, e);
+                    return "error".to_string();
+                }
+            };
+            match relink(file, |tmp| std::os::unix::fs::symlink(&target, tmp)) {
+                Ok(()) => "symlinked".to_string(),
+                Err(e) => {
+                    eprintln!("Warning: Failed to symlink '{}' to '{}': {}", file, keeper, e);
+                    "error".to_string()
+                }
+            }
+        }
+    }
+}
+
+/// Replaces `file` with a link pointing back at the kept file, without ever
+/// leaving a gap where neither the original nor the link exists: `make_link`
+/// (a `fs::hard_link`/`symlink` call bound to the kept file) creates the
+/// replacement at a temporary path first, and only once that succeeds is it
+/// renamed over `file`.
+fn relink(file: &str, make_link: impl FnOnce(&std::path::Path) -> std::io::Result<()>) -> std::io::Result<()> {
+    let tmp_path = temp_link_path(file);
+    make_link(&tmp_path)?;
+    fs::rename(&tmp_path, file)
+}
+
+/// A sibling path to `file` (same parent directory) used as the staging
+/// location for the replacement link before it's renamed into place.
+fn temp_link_path(file: &str) -> std::path::PathBuf {
+    let path = std::path::Path::new(file);
+    let tmp_name = match path.file_name() {
+        Some(name) => format!(".{}.redup-tmp", name.to_string_lossy()),
+        None => ".redup-tmp".to_string(),
+    };
+
+    match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.join(tmp_name),
+        _ => std::path::PathBuf::from(tmp_name),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    fn temp_dir() -> std::path::PathBuf {
+        static COUNTER: AtomicU32 = AtomicU32::new(0);
+        let dir = std::env::temp_dir().join(format!(
+            "redup-action-test-{}-{}",
+            std::process::id(),
+            COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn write_file(dir: &std::path::Path, name: &str, contents: &[u8]) -> String {
+        let path = dir.join(name);
+        fs::write(&path, contents).unwrap();
+        path.to_string_lossy().to_string()
+    }
+
+    fn set_mtime(file: &str, when: SystemTime) {
+        let times = std::fs::FileTimes::new().set_modified(when);
+        fs::File::options().write(true).open(file).unwrap().set_times(times).unwrap();
+    }
+
+    fn rt() -> tokio::runtime::Runtime {
+        tokio::runtime::Runtime::new().unwrap()
+    }
+
+    #[test]
+    fn pick_keeper_first_breaks_ties_on_path() {
+        let dir = temp_dir();
+        let b = write_file(&dir, "b.txt", b"x");
+        let a = write_file(&dir, "a.txt", b"x");
+        // Deliberately passed with `b` before `a` to prove order doesn't matter.
+        let files = vec![b, a.clone()];
+        let keeper = rt().block_on(pick_keeper(&files, KeepPolicy::First));
+        assert_eq!(keeper, a);
+    }
+
+    #[test]
+    fn pick_keeper_oldest_breaks_equal_mtime_ties_on_path() {
+        let dir = temp_dir();
+        let b = write_file(&dir, "b.txt", b"x");
+        let a = write_file(&dir, "a.txt", b"x");
+        let same_instant = SystemTime::now() - std::time::Duration::from_secs(60);
+        set_mtime(&b, same_instant);
+        set_mtime(&a, same_instant);
+        let keeper = rt().block_on(pick_keeper(&[b, a.clone()], KeepPolicy::Oldest));
+        assert_eq!(keeper, a);
+    }
+
+    #[test]
+    fn pick_keeper_newest_breaks_equal_mtime_ties_on_path() {
+        let dir = temp_dir();
+        let b = write_file(&dir, "b.txt", b"x");
+        let a = write_file(&dir, "a.txt", b"x");
+        let same_instant = SystemTime::now() - std::time::Duration::from_secs(60);
+        set_mtime(&b, same_instant);
+        set_mtime(&a, same_instant);
+        let keeper = rt().block_on(pick_keeper(&[b, a.clone()], KeepPolicy::Newest));
+        assert_eq!(keeper, a);
+    }
+
+    #[test]
+    fn pick_keeper_oldest_prefers_lower_mtime() {
+        let dir = temp_dir();
+        let older = write_file(&dir, "older.txt", b"x");
+        let newer = write_file(&dir, "newer.txt", b"x");
+        let now = SystemTime::now();
+        set_mtime(&older, now - std::time::Duration::from_secs(120));
+        set_mtime(&newer, now - std::time::Duration::from_secs(1));
+        let keeper = rt().block_on(pick_keeper(&[newer, older.clone()], KeepPolicy::Oldest));
+        assert_eq!(keeper, older);
+    }
+
+    #[test]
+    fn pick_keeper_shortest_path_breaks_length_ties_on_path() {
+        let dir = temp_dir();
+        let b = write_file(&dir, "bb.txt", b"x");
+        let a = write_file(&dir, "aa.txt", b"x");
+        // Both names are the same length, so the tie-break must fall back to path.
+        let keeper = rt().block_on(pick_keeper(&[b, a.clone()], KeepPolicy::ShortestPath));
+        assert_eq!(keeper, a);
+    }
+
+    #[test]
+    fn resolve_groups_delete_removes_non_keepers() {
+        let dir = temp_dir();
+        let a = write_file(&dir, "a.txt", b"x");
+        let b = write_file(&dir, "b.txt", b"x");
+        let mut groups = HashMap::new();
+        groups.insert("hash".to_string(), vec![a.clone(), b.clone()]);
+
+        let resolved = rt().block_on(resolve_groups(groups, DedupAction::Delete, KeepPolicy::First, false, false));
+        let outcomes = &resolved["hash"];
+
+        assert!(outcomes.iter().any(|o| o.file == a && o.action == "kept"));
+        assert!(outcomes.iter().any(|o| o.file == b && o.action == "deleted"));
+        assert!(std::path::Path::new(&a).exists());
+        assert!(!std::path::Path::new(&b).exists());
+    }
+
+    #[test]
+    fn resolve_groups_dry_run_leaves_filesystem_untouched() {
+        let dir = temp_dir();
+        let a = write_file(&dir, "a.txt", b"x");
+        let b = write_file(&dir, "b.txt", b"x");
+        let mut groups = HashMap::new();
+        groups.insert("hash".to_string(), vec![a.clone(), b.clone()]);
+
+        let resolved = rt().block_on(resolve_groups(groups, DedupAction::Delete, KeepPolicy::First, true, false));
+        let outcomes = &resolved["hash"];
+
+        assert!(outcomes.iter().any(|o| o.file == b && o.action == "would-delete"));
+        assert!(std::path::Path::new(&a).exists());
+        assert!(std::path::Path::new(&b).exists());
+    }
+
+    #[test]
+    fn resolve_groups_hardlink_points_non_keepers_at_keeper() {
+        let dir = temp_dir();
+        let a = write_file(&dir, "a.txt", b"x");
+        let b = write_file(&dir, "b.txt", b"x");
+        let mut groups = HashMap::new();
+        groups.insert("hash".to_string(), vec![a.clone(), b.clone()]);
+
+        rt().block_on(resolve_groups(groups, DedupAction::Hardlink, KeepPolicy::First, false, false));
+
+        let meta_a = fs::metadata(&a).unwrap();
+        let meta_b = fs::metadata(&b).unwrap();
+        assert_eq!(
+            std::os::unix::fs::MetadataExt::ino(&meta_a),
+            std::os::unix::fs::MetadataExt::ino(&meta_b)
+        );
+    }
+
+    #[test]
+    fn resolve_groups_symlink_points_non_keepers_at_keeper() {
+        let dir = temp_dir();
+        let a = write_file(&dir, "a.txt", b"x");
+        let b = write_file(&dir, "b.txt", b"x");
+        let mut groups = HashMap::new();
+        groups.insert("hash".to_string(), vec![a.clone(), b.clone()]);
+
+        rt().block_on(resolve_groups(groups, DedupAction::Symlink, KeepPolicy::First, false, false));
+
+        let target = fs::read_link(&b).unwrap();
+        assert_eq!(target, fs::canonicalize(&a).unwrap());
+    }
+}
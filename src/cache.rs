@@ -0,0 +1,105 @@
+use std::collections::HashMap;
+use std::fs::Metadata;
+use std::time::UNIX_EPOCH;
+
+use rusqlite::{params, Connection};
+
+use crate::debug_log;
+
+/// A cached digest for a file, keyed by its canonical path, tagged with the
+/// size/mtime pair it was computed against so a stale cache entry is never
+/// silently reused. `algorithm` records which `--algorithm` produced
+/// `digest`, since digests from different algorithms are not comparable.
+#[derive(Debug, Clone)]
+pub struct CacheEntry {
+    pub size: u64,
+    pub mtime_nanos: i64,
+    pub digest: String,
+    pub algorithm: String,
+}
+
+/// Loads the on-disk hash cache, creating the backing table if `path`
+/// doesn't exist yet.
+pub fn load_cache(path: &str) -> Result<HashMap<String, CacheEntry>, Box<dyn std::error::Error>> {
+    let conn = Connection::open(path)?;
+    ensure_schema(&conn)?;
+
+    let mut stmt = conn.prepare("SELECT path, size, mtime, digest, algorithm FROM cache")?;
+    let rows = stmt.query_map([], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            CacheEntry {
+                size: row.get::<_, i64>(1)? as u64,
+                mtime_nanos: row.get(2)?,
+                digest: row.get(3)?,
+                algorithm: row.get(4)?,
+            },
+        ))
+    })?;
+
+    let mut cache = HashMap::new();
+    for row in rows {
+        let (path, entry) = row?;
+        cache.insert(path, entry);
+    }
+    Ok(cache)
+}
+
+/// Overwrites the on-disk cache with `entries`.
+pub fn save_cache(path: &str, entries: &HashMap<String, CacheEntry>) -> Result<(), Box<dyn std::error::Error>> {
+    let conn = Connection::open(path)?;
+    ensure_schema(&conn)?;
+    conn.execute("DELETE FROM cache", [])?;
+
+    for (path_key, entry) in entries {
+        conn.execute(
+            "INSERT OR REPLACE INTO cache (path, size, mtime, digest, algorithm) VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![path_key, entry.size as i64, entry.mtime_nanos, entry.digest, entry.algorithm],
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Drops cache entries whose path no longer exists or whose size/mtime no
+/// longer matches what's on disk, so stale entries don't accumulate.
+pub async fn rebase_cache(entries: HashMap<String, CacheEntry>, verbose: bool) -> HashMap<String, CacheEntry> {
+    let mut kept = HashMap::with_capacity(entries.len());
+
+    for (path, entry) in entries {
+        let still_valid = match tokio::fs::metadata(&path).await {
+            Ok(metadata) => metadata.len() == entry.size && mtime_nanos(&metadata) == Some(entry.mtime_nanos),
+            Err(_) => false,
+        };
+
+        if still_valid {
+            kept.insert(path, entry);
+        } else {
+            debug_log(verbose, &format!("Rebase dropped stale cache entry: {}", path));
+        }
+    }
+
+    kept
+}
+
+/// Nanosecond-precision modification time, used as half of the cache's
+/// staleness check alongside file size.
+pub fn mtime_nanos(metadata: &Metadata) -> Option<i64> {
+    let modified = metadata.modified().ok()?;
+    let duration = modified.duration_since(UNIX_EPOCH).ok()?;
+    i64::try_from(duration.as_nanos()).ok()
+}
+
+fn ensure_schema(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS cache (
+            path TEXT PRIMARY KEY,
+            size INTEGER NOT NULL,
+            mtime INTEGER NOT NULL,
+            digest TEXT NOT NULL,
+            algorithm TEXT NOT NULL
+        )",
+        [],
+    )?;
+    Ok(())
+}
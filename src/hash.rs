@@ -0,0 +1,153 @@
+use sha2::Digest;
+use tokio::fs::File;
+use tokio::io::{AsyncReadExt, BufReader};
+
+use crate::{debug_log, HashAlgorithm};
+
+/// Number of leading bytes read for the cheap phase-2 "partial hash" pass.
+pub const PARTIAL_HASH_BLOCK_SIZE: usize = 4096;
+
+/// A streaming digest over file content. Lets `hash_partial`/`hash_full`
+/// feed bytes through whichever algorithm the user selected without caring
+/// about the concrete hasher type.
+pub trait FileHasher: Send {
+    fn update(&mut self, data: &[u8]);
+    fn finish_hex(self: Box<Self>) -> String;
+}
+
+struct Xxh3Hasher(xxhash_rust::xxh3::Xxh3);
+
+impl FileHasher for Xxh3Hasher {
+    fn update(&mut self, data: &[u8]) {
+        self.0.update(data);
+    }
+
+    fn finish_hex(self: Box<Self>) -> String {
+        format!("{:032x}", self.0.digest128())
+    }
+}
+
+struct Blake3Hasher(blake3::Hasher);
+
+impl FileHasher for Blake3Hasher {
+    fn update(&mut self, data: &[u8]) {
+        self.0.update(data);
+    }
+
+    fn finish_hex(self: Box<Self>) -> String {
+        self.0.finalize().to_hex().to_string()
+    }
+}
+
+struct Crc32Hasher(crc32fast::Hasher);
+
+impl FileHasher for Crc32Hasher {
+    fn update(&mut self, data: &[u8]) {
+        self.0.update(data);
+    }
+
+    fn finish_hex(self: Box<Self>) -> String {
+        format!("{:08x}", self.0.finalize())
+    }
+}
+
+struct Sha256Hasher(sha2::Sha256);
+
+impl FileHasher for Sha256Hasher {
+    fn update(&mut self, data: &[u8]) {
+        Digest::update(&mut self.0, data);
+    }
+
+    fn finish_hex(self: Box<Self>) -> String {
+        format!("{:x}", self.0.finalize())
+    }
+}
+
+fn new_hasher(algorithm: HashAlgorithm) -> Box<dyn FileHasher> {
+    match algorithm {
+        HashAlgorithm::Xxh3 => Box::new(Xxh3Hasher(xxhash_rust::xxh3::Xxh3::new())),
+        HashAlgorithm::Blake3 => Box::new(Blake3Hasher(blake3::Hasher::new())),
+        HashAlgorithm::Crc32 => Box::new(Crc32Hasher(crc32fast::Hasher::new())),
+        HashAlgorithm::Sha256 => Box::new(Sha256Hasher(sha2::Sha256::new())),
+    }
+}
+
+/// Hashes the first `PARTIAL_HASH_BLOCK_SIZE` bytes of a file, or its entire
+/// content if it is shorter than that. Used to cheaply narrow down
+/// same-size candidates before paying for a full read.
+pub async fn hash_partial(
+    file_path: &str,
+    algorithm: HashAlgorithm,
+    verbose: bool,
+) -> Result<String, Box<dyn std::error::Error>> {
+    debug_log(verbose, &format!("Partial-hashing file: {}", file_path));
+
+    let file = match File::open(file_path).await {
+        Ok(f) => f,
+        Err(e) => {
+            eprintln!("Warning: Failed to open file '{}': {}", file_path, e);
+            return Err(Box::new(e));
+        }
+    };
+
+    let mut reader = BufReader::new(file);
+    let mut hasher = new_hasher(algorithm);
+    let mut buffer = vec![0u8; PARTIAL_HASH_BLOCK_SIZE];
+    let mut total_bytes = 0usize;
+
+    while total_bytes < PARTIAL_HASH_BLOCK_SIZE {
+        match reader.read(&mut buffer[total_bytes..]).await {
+            Ok(0) => break,
+            Ok(bytes_read) => total_bytes += bytes_read,
+            Err(e) => {
+                eprintln!("Warning: Failed to read from file '{}': {}", file_path, e);
+                return Err(Box::new(e));
+            }
+        }
+    }
+
+    hasher.update(&buffer[..total_bytes]);
+    let hash = hasher.finish_hex();
+    debug_log(verbose, &format!("Partial-hashed {} bytes from {}, hash={}", total_bytes, file_path, hash));
+    Ok(hash)
+}
+
+/// Hashes the full content of a file, streaming it in fixed-size chunks.
+pub async fn hash_full(
+    file_path: &str,
+    algorithm: HashAlgorithm,
+    verbose: bool,
+) -> Result<String, Box<dyn std::error::Error>> {
+    debug_log(verbose, &format!("Full-hashing file: {}", file_path));
+
+    let file = match File::open(file_path).await {
+        Ok(f) => f,
+        Err(e) => {
+            eprintln!("Warning: Failed to open file '{}': {}", file_path, e);
+            return Err(Box::new(e));
+        }
+    };
+
+    let mut reader = BufReader::new(file);
+    let mut hasher = new_hasher(algorithm);
+    let mut buffer = vec![0u8; 8192];
+    let mut total_bytes = 0u64;
+
+    loop {
+        match reader.read(&mut buffer).await {
+            Ok(0) => break,
+            Ok(bytes_read) => {
+                total_bytes += bytes_read as u64;
+                hasher.update(&buffer[..bytes_read]);
+            }
+            Err(e) => {
+                eprintln!("Warning: Failed to read from file '{}': {}", file_path, e);
+                return Err(Box::new(e));
+            }
+        }
+    }
+
+    let hash = hasher.finish_hex();
+    debug_log(verbose, &format!("Full-hashed {} bytes from {}, hash={}", total_bytes, file_path, hash));
+    Ok(hash)
+}
@@ -1,19 +1,23 @@
-use std::hash::{DefaultHasher, Hash, Hasher};
 use std::collections::HashMap;
-use std::path::PathBuf;
 use std::process::exit;
-use std::fs::canonicalize;
 use std::env::{Args, args};
 use std::io::Write;
-use std::sync::Arc;
 
 use csv::Writer;
 use rusqlite::{Connection, params};
-use tokio::fs::File;
-use tokio::io::{AsyncReadExt, BufReader, stdin};
-use tokio::sync::{Semaphore, mpsc};
-use tokio::task::JoinSet;
-use walkdir::WalkDir;
+use tokio::io::{AsyncReadExt, stdin};
+
+mod action;
+mod cache;
+mod hash;
+mod pipeline;
+mod progress;
+mod verify;
+mod walk;
+
+use progress::ProgressReporter;
+
+use action::{DedupAction, KeepPolicy};
 
 const VERSION: &str = env!("REDUP_VERSION");
 
@@ -30,6 +34,21 @@ Options:
     -v, --verbose           Show detailed progress
     -o, --output <FILE>     Output file (default: stdout)
     -f, --format <FORMAT>   Output format: txt, csv, db (default: txt)
+    -a, --algorithm <ALGO>  Hash algorithm: xxh3, blake3, crc32, sha256 (default: xxh3)
+    --save <FILE>           Save computed digests to a hash cache
+    --load <FILE>           Reuse digests from a hash cache instead of re-reading unchanged files
+    --rebase                Drop cache entries whose path/size/mtime no longer match (use with --load; persists to --save if given, otherwise back to --load's file)
+    --verify                Confirm duplicates with a byte-for-byte comparison (always on for db format when --by hash)
+    --action <ACTION>       What to do with duplicates: report, delete, hardlink, symlink (default: report; forces --verify when --by is name or size)
+    --keep <POLICY>         Which file to keep: first, oldest, newest, shortest-path (default: first)
+    --dry-run               Print what --action would do without touching the filesystem
+    --by <METHOD>           Candidate grouping: name, size, hash (default: hash)
+    --min-size <BYTES>      Skip files smaller than BYTES
+    --max-size <BYTES>      Skip files larger than BYTES
+    --include-ext <LIST>    Only consider files with these comma-separated extensions
+    --exclude-ext <LIST>    Skip files with these comma-separated extensions
+    --exclude-dir <NAME>    Prune directories named NAME from the walk (repeatable)
+    --progress              Print periodic stage/ETA updates to stderr (distinct from --verbose)
     -V, --version           Show version message
     -h, --help              Show this help message
     --                      Read file paths from standard input (pipe ls output)
@@ -48,6 +67,17 @@ struct Config {
     directory: Option<String>,
     output: Option<String>,
     format: OutputFormat,
+    algorithm: HashAlgorithm,
+    save: Option<String>,
+    load: Option<String>,
+    rebase: bool,
+    verify: bool,
+    action: DedupAction,
+    keep: KeepPolicy,
+    dry_run: bool,
+    by: CheckingMethod,
+    filters: walk::Filters,
+    progress: bool,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -57,6 +87,52 @@ enum OutputFormat {
     Db,
 }
 
+/// How candidates are grouped into duplicate sets. `Hash` is today's
+/// content-based comparison; `Name`/`Size` are cheap proxies that skip
+/// hashing entirely (mirroring czkawka's checking methods).
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum CheckingMethod {
+    Name,
+    Size,
+    Hash,
+}
+
+/// Total number of progress stages a run goes through under `by`, counting
+/// the initial directory walk: `Name`/`Size` are a walk plus a single
+/// grouping pass, `Hash` is a walk plus the size/partial-hash/full-hash
+/// pipeline.
+pub(crate) fn stage_count(by: CheckingMethod) -> usize {
+    match by {
+        CheckingMethod::Name | CheckingMethod::Size => 2,
+        CheckingMethod::Hash => 4,
+    }
+}
+
+/// Hash algorithm used to fingerprint file contents. `Xxh3` is the default:
+/// a fast 128-bit non-cryptographic hash. `Blake3`/`Sha256` trade speed for
+/// a cryptographic guarantee against collisions; `Crc32` is kept around for
+/// parity with tools that only need a cheap 32-bit checksum.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum HashAlgorithm {
+    Xxh3,
+    Blake3,
+    Crc32,
+    Sha256,
+}
+
+impl HashAlgorithm {
+    /// Stable identifier used to tag cache entries, matching the `--algorithm`
+    /// CLI spelling so a cache file is self-describing.
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            HashAlgorithm::Xxh3 => "xxh3",
+            HashAlgorithm::Blake3 => "blake3",
+            HashAlgorithm::Crc32 => "crc32",
+            HashAlgorithm::Sha256 => "sha256",
+        }
+    }
+}
+
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     let rt = tokio::runtime::Runtime::new()?;
     rt.block_on(async { 
@@ -66,7 +142,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
-fn debug_log(verbose: bool, msg: &str) {
+pub(crate) fn debug_log(verbose: bool, msg: &str) {
     if verbose {
         eprintln!("[DEBUG] {}", msg);
     }
@@ -83,53 +159,76 @@ async fn run(mut args: Args) -> Result<(), Box<dyn std::error::Error>> {
     }
 
     let start_time = std::time::Instant::now();
-    let mut m: HashMap<u64, Vec<String>> = HashMap::new();
-    
-    if config.stdin_files {
-        let mut input = String::new();
-        let mut stdin = stdin();
-        if let Err(e) = stdin.read_to_string(&mut input).await {
-            eprintln!("Error: Failed to read from stdin: {}", e);
-            return Err(Box::new(e));
-        }
-        let files: Vec<&str> = input.lines().collect();
-        debug_log(config.verbose, &format!("Read {} lines from stdin", files.len()));
-        
-        if let Err(e) = find_duplicates_from_list(&mut m, &files, &config).await {
-            eprintln!("Error: Failed to process files from stdin: {}", e);
-            return Err(e);
-        }
-    } else {
-        match config.directory {
-            Some(ref dir) => {
-                if let Err(e) = find_duplicates_from_directory(&mut m, dir, &config).await {
-                    eprintln!("Error: Failed to scan directory '{}': {}", dir, e);
-                    return Err(e);
-                }
+    let mut m: HashMap<String, Vec<String>> = HashMap::new();
+    let (reporter, reporter_handle) = ProgressReporter::new(config.progress);
+
+    let scan_result: Result<(), Box<dyn std::error::Error>> = async {
+        if config.stdin_files {
+            let mut input = String::new();
+            let mut stdin = stdin();
+            if let Err(e) = stdin.read_to_string(&mut input).await {
+                eprintln!("Error: Failed to read from stdin: {}", e);
+                return Err(Box::new(e) as Box<dyn std::error::Error>);
+            }
+            let files: Vec<&str> = input.lines().collect();
+            debug_log(config.verbose, &format!("Read {} lines from stdin", files.len()));
+
+            if let Err(e) = find_duplicates_from_list(&mut m, &files, &config, &reporter).await {
+                eprintln!("Error: Failed to process files from stdin: {}", e);
+                return Err(e);
             }
-            None => {
-                eprintln!("{}", USAGE);
-                exit(1);
+        } else {
+            match config.directory {
+                Some(ref dir) => {
+                    if let Err(e) = find_duplicates_from_directory(&mut m, dir, &config, &reporter).await {
+                        eprintln!("Error: Failed to scan directory '{}': {}", dir, e);
+                        return Err(e);
+                    }
+                }
+                None => {
+                    eprintln!("{}", USAGE);
+                    exit(1);
+                }
             }
         }
+        Ok(())
+    }.await;
+
+    drop(reporter);
+    if let Some(handle) = reporter_handle {
+        let _ = handle.await;
     }
-    
+    scan_result?;
+
+    // `db` format forces a verification pass so its output can be trusted
+    // without `--verify`, but that only makes sense for `--by hash`: `name`
+    // and `size` groups aren't hashed at all (and `name` groups aren't even
+    // guaranteed to be same-size), so forcing a full read here would silently contradict
+    // the whole point of those cheap modes.
+    let force_verify = matches!(config.format, OutputFormat::Db) && matches!(config.by, CheckingMethod::Hash);
+    if config.verify || force_verify {
+        debug_log(config.verbose, "Running byte-exact verification pass");
+        m = verify::verify_groups(m, config.verbose).await;
+    }
+
     let total_files: usize = m.values().map(|v| v.len()).sum();
     let unique_hashes = m.len();
     let duplicate_groups = m.values().filter(|v| v.len() > 1).count();
     let duplicate_files: usize = m.values().filter(|v| v.len() > 1).map(|v| v.len()).sum();
-    
+
     debug_log(config.verbose, &format!("Total files processed: {}", total_files));
     debug_log(config.verbose, &format!("Unique hashes: {}", unique_hashes));
     debug_log(config.verbose, &format!("Duplicate groups: {}", duplicate_groups));
     debug_log(config.verbose, &format!("Duplicate files: {}", duplicate_files));
     debug_log(config.verbose, &format!("Elapsed time: {:?}", start_time.elapsed()));
-    
-    if let Err(e) = print_results(&mut m, &config) {
+
+    let mut resolved = action::resolve_groups(m, config.action, config.keep, config.dry_run, config.verbose).await;
+
+    if let Err(e) = print_results(&mut resolved, &config) {
         eprintln!("Error: Failed to print results: {}", e);
         return Err(e);
     }
-    
+
     Ok(())
 }
 
@@ -152,6 +251,17 @@ fn parse_args(args: &mut Args) -> Result<Config, Box<dyn std::error::Error>> {
         directory: None,
         output: None,
         format: OutputFormat::Text,
+        algorithm: HashAlgorithm::Xxh3,
+        save: None,
+        load: None,
+        rebase: false,
+        verify: false,
+        action: DedupAction::Report,
+        keep: KeepPolicy::First,
+        dry_run: false,
+        by: CheckingMethod::Hash,
+        filters: walk::Filters::default(),
+        progress: false,
     };
 
     let _ = args.next(); // Skip program name
@@ -197,6 +307,133 @@ fn parse_args(args: &mut Args) -> Result<Config, Box<dyn std::error::Error>> {
                     exit(1);
                 }
             }
+            "-a" | "--algorithm" => {
+                if let Some(algo_str) = args.next() {
+                    config.algorithm = match algo_str.to_lowercase().as_str() {
+                        "xxh3" => HashAlgorithm::Xxh3,
+                        "blake3" => HashAlgorithm::Blake3,
+                        "crc32" => HashAlgorithm::Crc32,
+                        "sha256" => HashAlgorithm::Sha256,
+                        _ => {
+                            eprintln!("Error: Unknown algorithm '{}'. Use: xxh3, blake3, crc32, or sha256", algo_str);
+                            exit(1);
+                        }
+                    };
+                } else {
+                    eprintln!("Error: --algorithm requires an algorithm (xxh3, blake3, crc32, sha256)");
+                    exit(1);
+                }
+            }
+            "--save" => {
+                if let Some(path) = args.next() {
+                    config.save = Some(path);
+                } else {
+                    eprintln!("Error: --save requires a file path");
+                    exit(1);
+                }
+            }
+            "--load" => {
+                if let Some(path) = args.next() {
+                    config.load = Some(path);
+                } else {
+                    eprintln!("Error: --load requires a file path");
+                    exit(1);
+                }
+            }
+            "--rebase" => config.rebase = true,
+            "--verify" => config.verify = true,
+            "--action" => {
+                if let Some(action_str) = args.next() {
+                    config.action = match action_str.to_lowercase().as_str() {
+                        "report" => DedupAction::Report,
+                        "delete" => DedupAction::Delete,
+                        "hardlink" => DedupAction::Hardlink,
+                        "symlink" => DedupAction::Symlink,
+                        _ => {
+                            eprintln!("Error: Unknown action '{}'. Use: report, delete, hardlink, or symlink", action_str);
+                            exit(1);
+                        }
+                    };
+                } else {
+                    eprintln!("Error: --action requires an action (report, delete, hardlink, symlink)");
+                    exit(1);
+                }
+            }
+            "--keep" => {
+                if let Some(keep_str) = args.next() {
+                    config.keep = match keep_str.to_lowercase().as_str() {
+                        "first" => KeepPolicy::First,
+                        "oldest" => KeepPolicy::Oldest,
+                        "newest" => KeepPolicy::Newest,
+                        "shortest-path" => KeepPolicy::ShortestPath,
+                        _ => {
+                            eprintln!("Error: Unknown keep policy '{}'. Use: first, oldest, newest, or shortest-path", keep_str);
+                            exit(1);
+                        }
+                    };
+                } else {
+                    eprintln!("Error: --keep requires a policy (first, oldest, newest, shortest-path)");
+                    exit(1);
+                }
+            }
+            "--dry-run" => config.dry_run = true,
+            "--by" => {
+                if let Some(by_str) = args.next() {
+                    config.by = match by_str.to_lowercase().as_str() {
+                        "name" => CheckingMethod::Name,
+                        "size" => CheckingMethod::Size,
+                        "hash" => CheckingMethod::Hash,
+                        _ => {
+                            eprintln!("Error: Unknown grouping method '{}'. Use: name, size, or hash", by_str);
+                            exit(1);
+                        }
+                    };
+                } else {
+                    eprintln!("Error: --by requires a method (name, size, hash)");
+                    exit(1);
+                }
+            }
+            "--min-size" => {
+                if let Some(size_str) = args.next() {
+                    config.filters.min_size = Some(parse_size_arg(&size_str, "--min-size"));
+                } else {
+                    eprintln!("Error: --min-size requires a byte count");
+                    exit(1);
+                }
+            }
+            "--max-size" => {
+                if let Some(size_str) = args.next() {
+                    config.filters.max_size = Some(parse_size_arg(&size_str, "--max-size"));
+                } else {
+                    eprintln!("Error: --max-size requires a byte count");
+                    exit(1);
+                }
+            }
+            "--include-ext" => {
+                if let Some(list) = args.next() {
+                    config.filters.include_ext = Some(parse_ext_list(&list));
+                } else {
+                    eprintln!("Error: --include-ext requires a comma-separated extension list");
+                    exit(1);
+                }
+            }
+            "--exclude-ext" => {
+                if let Some(list) = args.next() {
+                    config.filters.exclude_ext = Some(parse_ext_list(&list));
+                } else {
+                    eprintln!("Error: --exclude-ext requires a comma-separated extension list");
+                    exit(1);
+                }
+            }
+            "--exclude-dir" => {
+                if let Some(name) = args.next() {
+                    config.filters.exclude_dirs.push(name);
+                } else {
+                    eprintln!("Error: --exclude-dir requires a directory name");
+                    exit(1);
+                }
+            }
+            "--progress" => config.progress = true,
             "-V" | "--version" => print_version_and_exit(),
             "-h" | "--help" => print_usage_and_exit(),
             "--" => config.stdin_files = true,
@@ -212,214 +449,70 @@ fn parse_args(args: &mut Args) -> Result<Config, Box<dyn std::error::Error>> {
         }
     }
 
+    // `--by name`/`--by size` groups never compare file content, so handing
+    // them to a destructive `--action` risks deleting/relinking files that
+    // only coincidentally share a name or size. Force the byte-exact
+    // verification pass in that case rather than silently trusting the name
+    // or size match.
+    if !matches!(config.action, DedupAction::Report) && !matches!(config.by, CheckingMethod::Hash) {
+        config.verify = true;
+    }
+
     Ok(config)
 }
 
-async fn hash_file_contents(file_path: String, verbose: bool) -> Result<(u64, String), Box<dyn std::error::Error>> {
-    debug_log(verbose, &format!("Opening file: {}", file_path));
-    
-    let file = match File::open(&file_path).await {
-        Ok(f) => f,
-        Err(e) => {
-            eprintln!("Warning: Failed to open file '{}': {}", file_path, e);
-            return Err(Box::new(e));
-        }
-    };
-    
-    let mut reader = BufReader::new(file);
-    let mut hasher = DefaultHasher::new();
-    let mut buffer = vec![0u8; 8192];
-    let mut total_bytes = 0u64;
-
-    loop {
-        match reader.read(&mut buffer).await {
-            Ok(bytes_read) => {
-                if bytes_read == 0 {
-                    break;
-                }
-                total_bytes += bytes_read as u64;
-                buffer[..bytes_read].hash(&mut hasher);
-            }
-            Err(e) => {
-                eprintln!("Warning: Failed to read from file '{}': {}", file_path, e);
-                return Err(Box::new(e));
-            }
+fn parse_size_arg(size_str: &str, flag: &str) -> u64 {
+    match size_str.parse() {
+        Ok(size) => size,
+        Err(_) => {
+            eprintln!("Error: {} expects a byte count, got '{}'", flag, size_str);
+            exit(1);
         }
     }
+}
 
-    let hash = hasher.finish();
-    debug_log(verbose, &format!("Hashed {} bytes from {}, hash={:x}", total_bytes, file_path, hash));
-    Ok((hash, file_path))
+fn parse_ext_list(list: &str) -> Vec<String> {
+    list.split(',')
+        .map(|ext| ext.trim().trim_start_matches('.').to_lowercase())
+        .filter(|ext| !ext.is_empty())
+        .collect()
 }
 
 async fn find_duplicates_from_directory(
-    m: &mut HashMap<u64, Vec<String>>, 
-    directory: &str, 
-    config: &Config
+    m: &mut HashMap<String, Vec<String>>,
+    directory: &str,
+    config: &Config,
+    reporter: &ProgressReporter,
 ) -> Result<(), Box<dyn std::error::Error>> {
     let verbose = config.verbose;
     debug_log(verbose, &format!("Starting directory scan: {}", directory));
-    let (tx, mut rx) = mpsc::channel::<PathBuf>(1000);
-    let semaphore = Arc::new(Semaphore::new(100));
-    let mut join_set = JoinSet::new();
-
-    // Spawn directory walker task
-    let directory_owned: String = directory.to_string();
-    let verbose_flag: bool = verbose;
-    let walker_handle = tokio::task::spawn_blocking(move || {
-        let mut file_count: usize = 0;
-        for entry in WalkDir::new(&directory_owned).into_iter().flatten() {
-            let abs_path: PathBuf = entry.path().to_path_buf();
-            if abs_path.is_dir() {
-                if verbose_flag {
-                    println!("Searching...\n\t{}", abs_path.display());
-                }
-            } else {
-                file_count += 1;
-                if verbose_flag {
-                    println!("\tFound file...\n\t\t{}", abs_path.display());
-                }
-                if tx.blocking_send(abs_path).is_err() {
-                    break;
-                }
-            }
-        }
-        debug_log(verbose_flag, &format!("Walker finished. Found {} files", file_count));
-    });
-
-    // Process files as they're discovered
-    let mut queued_files = 0usize;
-    while let Some(path) = rx.recv().await {
-        let abs_path_s = path.to_string_lossy().to_string();
-        debug_log(verbose, &format!("Queueing file for hashing: {}", abs_path_s));
-        let permit: tokio::sync::OwnedSemaphorePermit = semaphore.clone().acquire_owned().await?;
-        
-        queued_files += 1;
-        join_set.spawn(async move {
-            let _permit = permit;
-            (hash_file_contents(abs_path_s, verbose).await).ok()
-        });
-    }
-    debug_log(verbose, &format!("Queued {} files for hashing", queued_files));
-
-    // Wait for walker to complete
-    let _ = walker_handle.await;
-
-    // Collect all results
-    let mut completed_tasks = 0usize;
-    let mut failed_tasks = 0usize;
-    while let Some(result) = join_set.join_next().await {
-        match result {
-            Ok(Some((hash, path))) => {
-                completed_tasks += 1;
-                debug_log(verbose, &format!("Task completed: {} -> {:x}", path, hash));
-                m.entry(hash).or_default().push(path);
-            }
-            Ok(None) => {
-                failed_tasks += 1;
-                debug_log(verbose, "Task completed but returned None (failed to hash)");
-            }
-            Err(e) => {
-                failed_tasks += 1;
-                debug_log(verbose, &format!("Task panicked or failed: {:?}", e));
-            }
-        }
-    }
-    debug_log(verbose, &format!("Completed: {} tasks, Failed: {} tasks", completed_tasks, failed_tasks));
+
+    let paths = walk::collect_paths_from_directory(directory, &config.filters, reporter, stage_count(config.by), verbose).await?;
+    debug_log(verbose, &format!("Walk found {} candidate file(s)", paths.len()));
+
+    *m = pipeline::run_pipeline(paths, config, reporter).await?;
 
     Ok(())
 }
 
 async fn find_duplicates_from_list(
-    m: &mut HashMap<u64, Vec<String>>, 
-    files: &[&str], 
-    config: &Config
+    m: &mut HashMap<String, Vec<String>>,
+    files: &[&str],
+    config: &Config,
+    reporter: &ProgressReporter,
 ) -> Result<(), Box<dyn std::error::Error>> {
     let verbose = config.verbose;
     debug_log(verbose, &format!("Processing {} files from stdin", files.len()));
-    let (tx, mut rx) = mpsc::channel::<PathBuf>(1000);
-    let semaphore = Arc::new(Semaphore::new(100));
-    let mut join_set = JoinSet::new();
-    let verbose_flag: bool = verbose;
-
-    // Spawn walker task to collect files
-    let files_owned: Vec<String> = files.iter().map(|&s| s.to_string()).collect();
-    let walker_handle = tokio::task::spawn_blocking(move || {
-        let mut file_count: usize = 0;
-        for file_path in files_owned {
-            if file_path.is_empty() {
-                continue;
-            }
-            
-            if let Ok(abs_path) = canonicalize(&file_path) {
-                if abs_path.is_dir() {
-                    if verbose_flag {
-                        println!("Searching directory...\n\t{}", abs_path.display());
-                    }
-                    for entry in WalkDir::new(&abs_path).into_iter().flatten() {
-                        let file_abs_path: PathBuf = entry.path().to_path_buf();
-                        if !file_abs_path.is_dir() && tx.blocking_send(file_abs_path).is_err() {
-                            return;
-                        }
-                    }
-                } else {
-                    file_count += 1;
-                    if verbose_flag {
-                        println!("Processing file...\n\t{}", abs_path.display());
-                    }
-                    if tx.blocking_send(abs_path).is_err() {
-                        return;
-                    }
-                }
-            }
-        }
-        debug_log(verbose_flag, &format!("Walker finished. Found {} files", file_count));
-    });
-
-    // Process files as they're discovered
-    let mut queued_files = 0usize;
-    while let Some(path) = rx.recv().await {
-        let abs_path_s = path.to_string_lossy().to_string();
-        debug_log(verbose, &format!("Queueing file for hashing: {}", abs_path_s));
-        let permit: tokio::sync::OwnedSemaphorePermit = semaphore.clone().acquire_owned().await?;
-        
-        queued_files += 1;
-        join_set.spawn(async move {
-            let _permit = permit;
-            (hash_file_contents(abs_path_s, verbose).await).ok()
-        });
-    }
-    debug_log(verbose, &format!("Queued {} files for hashing", queued_files));
-
-    // Wait for walker to complete
-    let _ = walker_handle.await;
-
-    // Collect all results
-    let mut completed_tasks = 0usize;
-    let mut failed_tasks = 0usize;
-    while let Some(result) = join_set.join_next().await {
-        match result {
-            Ok(Some((hash, path))) => {
-                completed_tasks += 1;
-                debug_log(verbose, &format!("Task completed: {} -> {:x}", path, hash));
-                m.entry(hash).or_default().push(path);
-            }
-            Ok(None) => {
-                failed_tasks += 1;
-                debug_log(verbose, "Task completed but returned None (failed to hash)");
-            }
-            Err(e) => {
-                failed_tasks += 1;
-                debug_log(verbose, &format!("Task panicked or failed: {:?}", e));
-            }
-        }
-    }
-    debug_log(verbose, &format!("Completed: {} tasks, Failed: {} tasks", completed_tasks, failed_tasks));
+
+    let paths = walk::collect_paths_from_list(files, &config.filters, reporter, stage_count(config.by), verbose).await?;
+    debug_log(verbose, &format!("Walk found {} candidate file(s)", paths.len()));
+
+    *m = pipeline::run_pipeline(paths, config, reporter).await?;
 
     Ok(())
 }
 
-fn print_results(m: &mut HashMap<u64, Vec<String>>, config: &Config) -> Result<(), Box<dyn std::error::Error>> {
+fn print_results(m: &mut HashMap<String, Vec<action::FileOutcome>>, config: &Config) -> Result<(), Box<dyn std::error::Error>> {
     debug_log(config.verbose, &format!("Printing results in {:?} format", config.format));
     match config.format {
         OutputFormat::Text => print_results_text(m, config),
@@ -428,7 +521,7 @@ fn print_results(m: &mut HashMap<u64, Vec<String>>, config: &Config) -> Result<(
     }
 }
 
-fn print_results_text(m: &mut HashMap<u64, Vec<String>>, config: &Config) -> Result<(), Box<dyn std::error::Error>> {
+fn print_results_text(m: &mut HashMap<String, Vec<action::FileOutcome>>, config: &Config) -> Result<(), Box<dyn std::error::Error>> {
     let duplicates_found = m.values().any(|e| e.len() > 1);
 
     let mut output: Box<dyn Write + Send> = match &config.output {
@@ -449,26 +542,27 @@ fn print_results_text(m: &mut HashMap<u64, Vec<String>>, config: &Config) -> Res
             continue;
         }
         writeln!(output, "-")?;
-        for e in item {
-            writeln!(output, "{}", e)?;
+        for outcome in item {
+            writeln!(output, "[{}] {}", outcome.action, outcome.file)?;
         }
     }
 
     Ok(())
 }
 
-fn print_results_csv(m: &mut HashMap<u64, Vec<String>>, config: &Config) -> Result<(), Box<dyn std::error::Error>> {
-    let mut group_records: Vec<(String, String, u64)> = Vec::new();
+fn print_results_csv(m: &mut HashMap<String, Vec<action::FileOutcome>>, config: &Config) -> Result<(), Box<dyn std::error::Error>> {
+    let mut group_records: Vec<(String, String, u64, String)> = Vec::new();
     let mut group_id = 0u64;
 
-    for (hash, files) in m.iter() {
-        if files.len() > 1 {
+    for (hash, outcomes) in m.iter() {
+        if outcomes.len() > 1 {
             group_id += 1;
-            for file in files {
+            for outcome in outcomes {
                 group_records.push((
-                    format!("{:x}", hash),
-                    file.clone(),
+                    hash.clone(),
+                    outcome.file.clone(),
                     group_id,
+                    outcome.action.clone(),
                 ));
             }
         }
@@ -476,16 +570,16 @@ fn print_results_csv(m: &mut HashMap<u64, Vec<String>>, config: &Config) -> Resu
 
     if let Some(path) = &config.output {
         let mut writer = Writer::from_path(path)?;
-        writer.write_record(["hash", "file_path", "group_id"])?;
-        for (hash, file, gid) in group_records {
-            writer.write_record([hash, file, gid.to_string()])?;
+        writer.write_record(["hash", "file_path", "group_id", "action"])?;
+        for (hash, file, gid, action) in group_records {
+            writer.write_record([hash, file, gid.to_string(), action])?;
         }
         writer.flush()?;
     } else {
         let mut writer = Writer::from_writer(std::io::stdout());
-        writer.write_record(["hash", "file_path", "group_id"])?;
-        for (hash, file, gid) in group_records {
-            writer.write_record([hash, file, gid.to_string()])?;
+        writer.write_record(["hash", "file_path", "group_id", "action"])?;
+        for (hash, file, gid, action) in group_records {
+            writer.write_record([hash, file, gid.to_string(), action])?;
         }
         writer.flush()?;
     }
@@ -501,7 +595,7 @@ fn print_results_csv(m: &mut HashMap<u64, Vec<String>>, config: &Config) -> Resu
     Ok(())
 }
 
-fn print_results_db(m: &mut HashMap<u64, Vec<String>>, config: &Config) -> Result<(), Box<dyn std::error::Error>> {
+fn print_results_db(m: &mut HashMap<String, Vec<action::FileOutcome>>, config: &Config) -> Result<(), Box<dyn std::error::Error>> {
     let db_path = match &config.output {
         Some(path) => path.clone(),
         None => {
@@ -531,24 +625,25 @@ fn print_results_db(m: &mut HashMap<u64, Vec<String>>, config: &Config) -> Resul
             id INTEGER PRIMARY KEY,
             group_id INTEGER NOT NULL,
             file_path TEXT NOT NULL,
+            action TEXT NOT NULL,
             FOREIGN KEY (group_id) REFERENCES duplicate_groups(id)
         )",
         [],
     )?;
 
     let mut group_count = 0;
-    for (hash, files) in m.iter() {
-        if files.len() > 1 {
+    for (hash, outcomes) in m.iter() {
+        if outcomes.len() > 1 {
             conn.execute(
                 "INSERT INTO duplicate_groups (hash) VALUES (?1)",
-                [format!("{:x}", hash)],
+                [hash],
             )?;
             let group_id = conn.last_insert_rowid();
 
-            for file in files {
+            for outcome in outcomes {
                 conn.execute(
-                    "INSERT INTO duplicate_files (group_id, file_path) VALUES (?1, ?2)",
-                    params![group_id, file],
+                    "INSERT INTO duplicate_files (group_id, file_path, action) VALUES (?1, ?2, ?3)",
+                    params![group_id, outcome.file, outcome.action],
                 )?;
             }
             group_count += 1;
@@ -0,0 +1,276 @@
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+use crate::cache::{mtime_nanos, CacheEntry};
+use crate::debug_log;
+use crate::hash::{hash_full, hash_partial, PARTIAL_HASH_BLOCK_SIZE};
+use crate::progress::ProgressReporter;
+use crate::{stage_count, CheckingMethod, Config, HashAlgorithm};
+
+const MAX_CONCURRENT_IO: usize = 100;
+
+/// Groups a flat list of candidate file paths into duplicate sets according
+/// to `config.by`.
+///
+/// `CheckingMethod::Hash` runs the full size -> partial-hash -> full-hash
+/// pipeline; `Name` and `Size` are cheap proxies that stop after a single
+/// pass over file names (`Name`) or file metadata (`Size`) and never read
+/// file contents.
+pub async fn run_pipeline(
+    paths: Vec<PathBuf>,
+    config: &Config,
+    reporter: &ProgressReporter,
+) -> Result<HashMap<String, Vec<String>>, Box<dyn std::error::Error>> {
+    let verbose = config.verbose;
+    let max_stage = stage_count(config.by);
+
+    match config.by {
+        CheckingMethod::Name => {
+            debug_log(verbose, "Grouping candidates by file name");
+            reporter.start_stage("name", 2, max_stage, paths.len());
+            Ok(group_by_name(paths, reporter))
+        }
+        CheckingMethod::Size => {
+            debug_log(verbose, "Grouping candidates by file size");
+            reporter.start_stage("size", 2, max_stage, paths.len());
+            let size_groups = group_by_size(paths, reporter, verbose).await?;
+            Ok(size_groups
+                .into_iter()
+                .map(|(size, files)| (size.to_string(), files))
+                .collect())
+        }
+        CheckingMethod::Hash => run_hash_pipeline(paths, config, reporter, max_stage).await,
+    }
+}
+
+/// The size -> partial-hash -> full-hash pipeline, returning the final
+/// duplicate groups keyed by full-content hash.
+///
+/// Files of different sizes are never compared against one another, and the
+/// vast majority of files are discarded after a metadata lookup or a single
+/// small read, so most of the tree is never fully read.
+async fn run_hash_pipeline(
+    paths: Vec<PathBuf>,
+    config: &Config,
+    reporter: &ProgressReporter,
+    max_stage: usize,
+) -> Result<HashMap<String, Vec<String>>, Box<dyn std::error::Error>> {
+    let verbose = config.verbose;
+    let algorithm = config.algorithm;
+
+    let mut cache = match &config.load {
+        Some(load_path) => {
+            let loaded = crate::cache::load_cache(load_path)?;
+            debug_log(verbose, &format!("Loaded {} cache entr{} from {}", loaded.len(), if loaded.len() == 1 { "y" } else { "ies" }, load_path));
+            if config.rebase {
+                crate::cache::rebase_cache(loaded, verbose).await
+            } else {
+                loaded
+            }
+        }
+        None => HashMap::new(),
+    };
+
+    debug_log(verbose, "Phase 1: grouping candidates by file size");
+    reporter.start_stage("size", 2, max_stage, paths.len());
+    let size_groups = group_by_size(paths, reporter, verbose).await?;
+    debug_log(verbose, &format!("{} size-group(s) survived phase 1", size_groups.len()));
+
+    debug_log(verbose, "Phase 2: grouping survivors by partial hash");
+    let partial_total: usize = size_groups.values().map(|files| files.len()).sum();
+    reporter.start_stage("partial-hash", 3, max_stage, partial_total);
+    let (partial_groups, mut m) = group_by_partial_hash(size_groups, algorithm, reporter, verbose, &cache).await?;
+    debug_log(verbose, &format!("{} partial-hash group(s) survived phase 2", partial_groups.len()));
+
+    debug_log(verbose, "Phase 3: confirming survivors with a full-content hash");
+    let full_total: usize = partial_groups.values().map(|files| files.len()).sum();
+    reporter.start_stage("full-hash", 4, max_stage, full_total);
+    for (digest, files) in group_by_full_hash(partial_groups, algorithm, reporter, verbose, &mut cache).await? {
+        m.entry(digest).or_default().extend(files);
+    }
+    m.retain(|_, files| files.len() > 1);
+
+    // `--rebase` without `--save` still needs to persist somewhere, or the
+    // stale rows it dropped just reappear next run: fall back to writing
+    // the rebased cache back to the `--load` path it came from.
+    let persist_path = config.save.as_ref().or(if config.rebase { config.load.as_ref() } else { None });
+    if let Some(persist_path) = persist_path {
+        crate::cache::save_cache(persist_path, &cache)?;
+        debug_log(verbose, &format!("Saved {} cache entr{} to {}", cache.len(), if cache.len() == 1 { "y" } else { "ies" }, persist_path));
+    }
+
+    Ok(m)
+}
+
+/// Phase 1: bucket files by size via `tokio::fs::metadata`, discarding any
+/// bucket that ends up with a single member since it cannot have a
+/// duplicate. Every zero-length file lands in the same bucket.
+async fn group_by_size(
+    paths: Vec<PathBuf>,
+    reporter: &ProgressReporter,
+    verbose: bool,
+) -> Result<HashMap<u64, Vec<String>>, Box<dyn std::error::Error>> {
+    let semaphore = Arc::new(Semaphore::new(MAX_CONCURRENT_IO));
+    let mut join_set = JoinSet::new();
+
+    for path in paths {
+        let permit = semaphore.clone().acquire_owned().await?;
+        join_set.spawn(async move {
+            let _permit = permit;
+            let metadata = tokio::fs::metadata(&path).await.ok()?;
+            Some((metadata.len(), path.to_string_lossy().to_string()))
+        });
+    }
+
+    let mut by_size: HashMap<u64, Vec<String>> = HashMap::new();
+    while let Some(result) = join_set.join_next().await {
+        reporter.file_checked(0);
+        if let Ok(Some((size, path))) = result {
+            debug_log(verbose, &format!("{} -> size {}", path, size));
+            by_size.entry(size).or_default().push(path);
+        }
+    }
+    by_size.retain(|_, files| files.len() > 1);
+
+    Ok(by_size)
+}
+
+/// Groups files by their base name alone, discarding any group that ends up
+/// with a single member. Cheapest of the three checking methods since it
+/// never touches file metadata.
+fn group_by_name(paths: Vec<PathBuf>, reporter: &ProgressReporter) -> HashMap<String, Vec<String>> {
+    let mut by_name: HashMap<String, Vec<String>> = HashMap::new();
+
+    for path in paths {
+        reporter.file_checked(0);
+        if let Some(name) = path.file_name() {
+            by_name
+                .entry(name.to_string_lossy().to_string())
+                .or_default()
+                .push(path.to_string_lossy().to_string());
+        }
+    }
+    by_name.retain(|_, files| files.len() > 1);
+
+    by_name
+}
+
+/// Phase 2: within each surviving size bucket, check the hash cache first;
+/// a file whose `(canonical_path, size, mtime)` matches a cache entry skips
+/// both this phase's partial read and phase 3's full read entirely and is
+/// placed straight into its final group under the cached digest. Every
+/// remaining file gets its leading block hashed and is regrouped by
+/// `(size, partial_hash)`.
+///
+/// A partial-hash group of one is normally dropped as a non-duplicate, but
+/// not for a size that also produced a cache hit: the lone file's read
+/// never ran against the cache hit's *partial* hash (that file skipped the
+/// read entirely), so it may still turn out to be a full-content match
+/// once phase 3 hashes it and the two are merged by digest.
+async fn group_by_partial_hash(
+    size_groups: HashMap<u64, Vec<String>>,
+    algorithm: HashAlgorithm,
+    reporter: &ProgressReporter,
+    verbose: bool,
+    cache: &HashMap<String, CacheEntry>,
+) -> Result<(HashMap<(u64, String), Vec<String>>, HashMap<String, Vec<String>>), Box<dyn std::error::Error>> {
+    let semaphore = Arc::new(Semaphore::new(MAX_CONCURRENT_IO));
+    let mut join_set = JoinSet::new();
+    let mut cached: HashMap<String, Vec<String>> = HashMap::new();
+    let mut sizes_with_cache_hit: HashSet<u64> = HashSet::new();
+
+    for (size, files) in size_groups {
+        for file in files {
+            if !cache.is_empty() {
+                let canonical = std::fs::canonicalize(&file).ok().map(|p| p.to_string_lossy().to_string());
+                let current_mtime = match tokio::fs::metadata(&file).await {
+                    Ok(metadata) => mtime_nanos(&metadata),
+                    Err(_) => None,
+                };
+
+                if let (Some(canonical), Some(current_mtime)) = (&canonical, current_mtime) {
+                    if let Some(entry) = cache.get(canonical) {
+                        if entry.size == size && entry.mtime_nanos == current_mtime && entry.algorithm == algorithm.as_str() {
+                            debug_log(verbose, &format!("Cache hit for {}", file));
+                            reporter.file_checked(0);
+                            sizes_with_cache_hit.insert(size);
+                            cached.entry(entry.digest.clone()).or_default().push(file);
+                            continue;
+                        }
+                    }
+                }
+            }
+
+            let permit = semaphore.clone().acquire_owned().await?;
+            join_set.spawn(async move {
+                let _permit = permit;
+                let hash = hash_partial(&file, algorithm, verbose).await.ok()?;
+                Some((size, hash, file))
+            });
+        }
+    }
+
+    let mut by_partial_hash: HashMap<(u64, String), Vec<String>> = HashMap::new();
+    while let Some(result) = join_set.join_next().await {
+        if let Ok(Some((size, hash, file))) = &result {
+            reporter.file_checked((*size).min(PARTIAL_HASH_BLOCK_SIZE as u64));
+            by_partial_hash.entry((*size, hash.clone())).or_default().push(file.clone());
+        } else {
+            reporter.file_checked(0);
+        }
+    }
+    by_partial_hash.retain(|(size, _), files| files.len() > 1 || sizes_with_cache_hit.contains(size));
+
+    Ok((by_partial_hash, cached))
+}
+
+/// Phase 3: files that still collide on both size and partial hash get a
+/// full-content hash to produce the final duplicate groups. Every file
+/// reaching this phase already missed the cache in phase 2, so it is always
+/// read; a freshly computed digest is recorded in `cache` for next run.
+async fn group_by_full_hash(
+    partial_groups: HashMap<(u64, String), Vec<String>>,
+    algorithm: HashAlgorithm,
+    reporter: &ProgressReporter,
+    verbose: bool,
+    cache: &mut HashMap<String, CacheEntry>,
+) -> Result<HashMap<String, Vec<String>>, Box<dyn std::error::Error>> {
+    let semaphore = Arc::new(Semaphore::new(MAX_CONCURRENT_IO));
+    let mut join_set = JoinSet::new();
+    let mut m: HashMap<String, Vec<String>> = HashMap::new();
+
+    for ((size, _partial_hash), files) in partial_groups {
+        for file in files {
+            let canonical = std::fs::canonicalize(&file).ok().map(|p| p.to_string_lossy().to_string());
+            let current_mtime = match tokio::fs::metadata(&file).await {
+                Ok(metadata) => mtime_nanos(&metadata),
+                Err(_) => None,
+            };
+
+            let permit = semaphore.clone().acquire_owned().await?;
+            join_set.spawn(async move {
+                let _permit = permit;
+                let digest = hash_full(&file, algorithm, verbose).await.ok()?;
+                Some((file, canonical, size, current_mtime, digest))
+            });
+        }
+    }
+
+    while let Some(result) = join_set.join_next().await {
+        if let Ok(Some((file, canonical, size, mtime, digest))) = result {
+            reporter.file_checked(size);
+            if let (Some(canonical), Some(mtime)) = (canonical, mtime) {
+                cache.insert(canonical, CacheEntry { size, mtime_nanos: mtime, digest: digest.clone(), algorithm: algorithm.as_str().to_string() });
+            }
+            m.entry(digest).or_default().push(file);
+        } else {
+            reporter.file_checked(0);
+        }
+    }
+
+    Ok(m)
+}
@@ -0,0 +1,154 @@
+use std::time::{Duration, Instant};
+
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+/// Minimum time between printed updates, so runs over huge trees don't
+/// flood the terminal with a line per file.
+const MIN_PRINT_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Snapshot of where a run currently stands, modeled on czkawka's
+/// `ProgressData`. `stage_index`/`max_stage` describe position within the
+/// overall pipeline (e.g. "partial-hash", stage 3 of 4); `files_checked`/
+/// `files_to_check` describe position within that stage alone.
+struct ProgressData {
+    current_stage: String,
+    stage_index: usize,
+    max_stage: usize,
+    files_checked: usize,
+    files_to_check: usize,
+    bytes_processed: u64,
+}
+
+enum ProgressEvent {
+    Stage { name: String, index: usize, max: usize, total_files: usize },
+    FileChecked { bytes: u64 },
+}
+
+/// Handle used by the walker and each pipeline phase to report progress.
+/// Cheap to clone into spawned tasks; when `--progress` wasn't passed the
+/// inner channel is `None` so every call is a no-op.
+#[derive(Clone)]
+pub struct ProgressReporter {
+    tx: Option<mpsc::UnboundedSender<ProgressEvent>>,
+}
+
+impl ProgressReporter {
+    /// Builds a reporter plus the background task that prints its updates.
+    /// When `enabled` is false there is no background task to join.
+    pub fn new(enabled: bool) -> (Self, Option<JoinHandle<()>>) {
+        if !enabled {
+            return (Self { tx: None }, None);
+        }
+
+        let (tx, rx) = mpsc::unbounded_channel();
+        let handle = tokio::spawn(run_reporter(rx));
+        (Self { tx: Some(tx) }, Some(handle))
+    }
+
+    /// Announces the start of a new stage (e.g. "scanning", "full-hash") and
+    /// how many files it expects to process. Pass `0` for `total_files` when
+    /// the count isn't known up front (e.g. the directory walk).
+    pub fn start_stage(&self, name: &str, index: usize, max: usize, total_files: usize) {
+        if let Some(tx) = &self.tx {
+            let _ = tx.send(ProgressEvent::Stage {
+                name: name.to_string(),
+                index,
+                max,
+                total_files,
+            });
+        }
+    }
+
+    /// Reports that one file finished the current stage, having read
+    /// `bytes` of content (`0` for stages that only touch metadata).
+    pub fn file_checked(&self, bytes: u64) {
+        if let Some(tx) = &self.tx {
+            let _ = tx.send(ProgressEvent::FileChecked { bytes });
+        }
+    }
+}
+
+/// Drains progress events and prints a rate-limited status line to stderr
+/// until every `ProgressReporter` clone has been dropped.
+async fn run_reporter(mut rx: mpsc::UnboundedReceiver<ProgressEvent>) {
+    let mut data = ProgressData {
+        current_stage: String::new(),
+        stage_index: 0,
+        max_stage: 0,
+        files_checked: 0,
+        files_to_check: 0,
+        bytes_processed: 0,
+    };
+    let mut stage_start = Instant::now();
+    let mut last_print = Instant::now() - MIN_PRINT_INTERVAL;
+
+    while let Some(event) = rx.recv().await {
+        match event {
+            ProgressEvent::Stage { name, index, max, total_files } => {
+                data.current_stage = name;
+                data.stage_index = index;
+                data.max_stage = max;
+                data.files_checked = 0;
+                data.files_to_check = total_files;
+                data.bytes_processed = 0;
+                stage_start = Instant::now();
+                print_status(&data, Duration::ZERO);
+                last_print = Instant::now();
+            }
+            ProgressEvent::FileChecked { bytes } => {
+                data.files_checked += 1;
+                data.bytes_processed += bytes;
+                if last_print.elapsed() >= MIN_PRINT_INTERVAL {
+                    print_status(&data, stage_start.elapsed());
+                    last_print = Instant::now();
+                }
+            }
+        }
+    }
+}
+
+fn print_status(data: &ProgressData, elapsed: Duration) {
+    let total = if data.files_to_check > 0 {
+        data.files_to_check.to_string()
+    } else {
+        "?".to_string()
+    };
+
+    eprintln!(
+        "[progress] stage {}/{} ({}): {}/{} files, {} processed, ETA {}",
+        data.stage_index,
+        data.max_stage,
+        data.current_stage,
+        data.files_checked,
+        total,
+        format_bytes(data.bytes_processed),
+        estimate_eta(data, elapsed),
+    );
+}
+
+fn estimate_eta(data: &ProgressData, elapsed: Duration) -> String {
+    if data.files_to_check == 0 || data.files_checked == 0 || data.files_checked >= data.files_to_check {
+        return "--".to_string();
+    }
+
+    let remaining_files = (data.files_to_check - data.files_checked) as f64;
+    let per_file_secs = elapsed.as_secs_f64() / data.files_checked as f64;
+    format!("{}s", (remaining_files * per_file_secs).round() as u64)
+}
+
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
@@ -0,0 +1,215 @@
+use std::collections::HashMap;
+
+use tokio::fs::File;
+use tokio::io::{AsyncReadExt, BufReader};
+
+use crate::debug_log;
+
+const COMPARE_CHUNK_SIZE: usize = 8192;
+
+/// Splits each candidate group into byte-identical sub-groups. Because the
+/// grouping key is only a hash digest (or, under `--by name`/`--by size`, a
+/// file name or size), two distinct files can share a key and be wrongly
+/// reported as duplicates; this pass compares bytes directly so
+/// reported duplicates are genuinely identical regardless of the hash
+/// algorithm chosen.
+pub async fn verify_groups(groups: HashMap<String, Vec<String>>, verbose: bool) -> HashMap<String, Vec<String>> {
+    let mut verified: HashMap<String, Vec<String>> = HashMap::new();
+
+    for (hash, files) in groups {
+        if files.len() <= 1 {
+            continue;
+        }
+
+        let clusters = split_by_content(&files, verbose).await;
+        debug_log(verbose, &format!("Group {} split into {} confirmed cluster(s)", hash, clusters.len()));
+
+        let mut suffix = 1u32;
+        for cluster in clusters {
+            if cluster.len() <= 1 {
+                continue;
+            }
+            let key = if suffix == 1 { hash.clone() } else { format!("{}#{}", hash, suffix) };
+            suffix += 1;
+            verified.insert(key, cluster);
+        }
+    }
+
+    verified
+}
+
+/// Partitions `files` (which share a grouping key, but not necessarily a
+/// size) into sub-groups that are byte-identical through EOF.
+async fn split_by_content(files: &[String], verbose: bool) -> Vec<Vec<String>> {
+    let mut clusters: Vec<Vec<String>> = Vec::new();
+
+    for file in files {
+        let mut joined = false;
+
+        for cluster in clusters.iter_mut() {
+            let representative = &cluster[0];
+            match files_equal(representative, file).await {
+                Ok(true) => {
+                    cluster.push(file.clone());
+                    joined = true;
+                    break;
+                }
+                Ok(false) => {}
+                Err(e) => {
+                    debug_log(verbose, &format!("Warning: Failed to compare '{}' and '{}': {}", representative, file, e));
+                }
+            }
+        }
+
+        if !joined {
+            clusters.push(vec![file.clone()]);
+        }
+    }
+
+    clusters
+}
+
+/// Compares two files byte-for-byte in lock-step chunks, returning `Ok(true)`
+/// only if every chunk matched through EOF on both sides.
+async fn files_equal(a: &str, b: &str) -> Result<bool, Box<dyn std::error::Error>> {
+    let mut reader_a = BufReader::new(File::open(a).await?);
+    let mut reader_b = BufReader::new(File::open(b).await?);
+
+    let mut buf_a = vec![0u8; COMPARE_CHUNK_SIZE];
+    let mut buf_b = vec![0u8; COMPARE_CHUNK_SIZE];
+
+    loop {
+        let read_a = fill_buffer(&mut reader_a, &mut buf_a).await?;
+        let read_b = fill_buffer(&mut reader_b, &mut buf_b).await?;
+
+        if read_a != read_b || buf_a[..read_a] != buf_b[..read_b] {
+            return Ok(false);
+        }
+        if read_a == 0 {
+            return Ok(true);
+        }
+    }
+}
+
+/// Reads into `buf` until it is completely full or EOF is reached, looping
+/// over short reads (legal even on regular files) so a partial read is
+/// never mistaken for a content mismatch.
+async fn fill_buffer(reader: &mut BufReader<File>, buf: &mut [u8]) -> std::io::Result<usize> {
+    let mut total = 0;
+    while total < buf.len() {
+        let read = reader.read(&mut buf[total..]).await?;
+        if read == 0 {
+            break;
+        }
+        total += read;
+    }
+    Ok(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    fn temp_dir() -> std::path::PathBuf {
+        static COUNTER: AtomicU32 = AtomicU32::new(0);
+        let dir = std::env::temp_dir().join(format!(
+            "redup-verify-test-{}-{}",
+            std::process::id(),
+            COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn write_file(dir: &std::path::Path, name: &str, contents: &[u8]) -> String {
+        let path = dir.join(name);
+        std::fs::write(&path, contents).unwrap();
+        path.to_string_lossy().to_string()
+    }
+
+    fn rt() -> tokio::runtime::Runtime {
+        tokio::runtime::Runtime::new().unwrap()
+    }
+
+    #[test]
+    fn files_equal_true_for_identical_content() {
+        let dir = temp_dir();
+        let a = write_file(&dir, "a.bin", &[1u8; COMPARE_CHUNK_SIZE + 37]);
+        let b = write_file(&dir, "b.bin", &[1u8; COMPARE_CHUNK_SIZE + 37]);
+        assert!(rt().block_on(files_equal(&a, &b)).unwrap());
+    }
+
+    #[test]
+    fn files_equal_false_on_eof_length_mismatch() {
+        // Same leading bytes (so they'd share a partial hash) but different
+        // length, exercising the EOF/short-read path in `fill_buffer`.
+        let dir = temp_dir();
+        let a = write_file(&dir, "a.bin", &[7u8; COMPARE_CHUNK_SIZE]);
+        let b = write_file(&dir, "b.bin", &[7u8; COMPARE_CHUNK_SIZE + 1]);
+        assert!(!rt().block_on(files_equal(&a, &b)).unwrap());
+    }
+
+    #[test]
+    fn files_equal_false_on_content_mismatch_past_first_chunk() {
+        let dir = temp_dir();
+        let content_a = vec![9u8; COMPARE_CHUNK_SIZE + 10];
+        let mut content_b = content_a.clone();
+        content_b[COMPARE_CHUNK_SIZE + 5] = 0;
+        let a = write_file(&dir, "a.bin", &content_a);
+        let b = write_file(&dir, "b.bin", &content_b);
+        assert!(!rt().block_on(files_equal(&a, &b)).unwrap());
+    }
+
+    #[test]
+    fn split_by_content_separates_a_false_hash_collision() {
+        let dir = temp_dir();
+        let a = write_file(&dir, "a.txt", b"identical");
+        let b = write_file(&dir, "b.txt", b"identical");
+        let c = write_file(&dir, "c.txt", b"different");
+        let files = vec![a.clone(), b.clone(), c.clone()];
+
+        let clusters = rt().block_on(split_by_content(&files, false));
+
+        assert_eq!(clusters.len(), 2);
+        let identical_cluster = clusters.iter().find(|cl| cl.contains(&a)).unwrap();
+        assert!(identical_cluster.contains(&b));
+        assert!(!identical_cluster.contains(&c));
+    }
+
+    #[test]
+    fn verify_groups_splits_false_collision_and_drops_singleton() {
+        let dir = temp_dir();
+        let a = write_file(&dir, "a.txt", b"identical");
+        let b = write_file(&dir, "b.txt", b"identical");
+        let c = write_file(&dir, "c.txt", b"different");
+
+        let mut groups = HashMap::new();
+        groups.insert("collided-hash".to_string(), vec![a.clone(), b.clone(), c.clone()]);
+
+        let verified = rt().block_on(verify_groups(groups, false));
+
+        // `c` diverges from `a`/`b` and ends up alone, so only the genuine
+        // duplicate pair survives; the lone `c` cluster is dropped.
+        assert_eq!(verified.len(), 1);
+        let cluster = verified.get("collided-hash").unwrap();
+        assert_eq!(cluster.len(), 2);
+        assert!(cluster.contains(&a));
+        assert!(cluster.contains(&b));
+    }
+
+    #[test]
+    fn verify_groups_keeps_whole_group_when_all_bytes_match() {
+        let dir = temp_dir();
+        let a = write_file(&dir, "a.txt", b"same");
+        let b = write_file(&dir, "b.txt", b"same");
+
+        let mut groups = HashMap::new();
+        groups.insert("hash".to_string(), vec![a.clone(), b.clone()]);
+
+        let verified = rt().block_on(verify_groups(groups, false));
+
+        assert_eq!(verified.len(), 1);
+        assert_eq!(verified.get("hash").unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,198 @@
+use std::fs::canonicalize;
+use std::path::{Path, PathBuf};
+
+use tokio::sync::mpsc;
+use walkdir::WalkDir;
+
+use crate::debug_log;
+use crate::progress::ProgressReporter;
+
+/// Filters applied while walking, so excluded subtrees and files are never
+/// even queued for hashing.
+#[derive(Debug, Clone, Default)]
+pub struct Filters {
+    pub min_size: Option<u64>,
+    pub max_size: Option<u64>,
+    pub include_ext: Option<Vec<String>>,
+    pub exclude_ext: Option<Vec<String>>,
+    pub exclude_dirs: Vec<String>,
+}
+
+impl Filters {
+    fn is_noop(&self) -> bool {
+        self.min_size.is_none()
+            && self.max_size.is_none()
+            && self.include_ext.is_none()
+            && self.exclude_ext.is_none()
+            && self.exclude_dirs.is_empty()
+    }
+
+    fn excludes_dir(&self, name: &std::ffi::OsStr) -> bool {
+        self.exclude_dirs.iter().any(|excluded| excluded.as_str() == name.to_string_lossy())
+    }
+
+    fn accepts(&self, path: &Path) -> bool {
+        if self.is_noop() {
+            return true;
+        }
+
+        let ext = path.extension().map(|e| e.to_string_lossy().to_lowercase());
+        if let Some(include) = &self.include_ext {
+            match &ext {
+                Some(ext) if include.iter().any(|e| e == ext) => {}
+                _ => return false,
+            }
+        }
+        if let Some(exclude) = &self.exclude_ext {
+            if let Some(ext) = &ext {
+                if exclude.iter().any(|e| e == ext) {
+                    return false;
+                }
+            }
+        }
+
+        if self.min_size.is_some() || self.max_size.is_some() {
+            let size = match std::fs::metadata(path) {
+                Ok(metadata) => metadata.len(),
+                Err(_) => return false,
+            };
+            if let Some(min) = self.min_size {
+                if size < min {
+                    return false;
+                }
+            }
+            if let Some(max) = self.max_size {
+                if size > max {
+                    return false;
+                }
+            }
+        }
+
+        true
+    }
+}
+
+/// Walks `directory` recursively and returns every regular file found that
+/// passes `filters`. Excluded directory subtrees are pruned rather than
+/// merely skipped, so they're never descended into. Reports each found file
+/// to `reporter` under a "scanning" stage (stage 1 of `total_stages`); the
+/// total file count isn't known until the walk finishes, so it's reported
+/// as unbounded.
+pub async fn collect_paths_from_directory(
+    directory: &str,
+    filters: &Filters,
+    reporter: &ProgressReporter,
+    total_stages: usize,
+    verbose: bool,
+) -> Result<Vec<PathBuf>, Box<dyn std::error::Error>> {
+    let (tx, mut rx) = mpsc::channel::<PathBuf>(1000);
+    let directory_owned = directory.to_string();
+    let filters_owned = filters.clone();
+    let reporter_owned = reporter.clone();
+
+    let walker_handle = tokio::task::spawn_blocking(move || {
+        reporter_owned.start_stage("scanning", 1, total_stages, 0);
+        let mut file_count = 0usize;
+        let walker = WalkDir::new(&directory_owned).into_iter().filter_entry(|entry| {
+            !entry.file_type().is_dir() || !filters_owned.excludes_dir(entry.file_name())
+        });
+        for entry in walker.flatten() {
+            let abs_path = entry.path().to_path_buf();
+            if abs_path.is_dir() {
+                if verbose {
+                    println!("Searching...\n\t{}", abs_path.display());
+                }
+            } else {
+                if !filters_owned.accepts(&abs_path) {
+                    continue;
+                }
+                file_count += 1;
+                reporter_owned.file_checked(0);
+                if verbose {
+                    println!("\tFound file...\n\t\t{}", abs_path.display());
+                }
+                if tx.blocking_send(abs_path).is_err() {
+                    break;
+                }
+            }
+        }
+        debug_log(verbose, &format!("Walker finished. Found {} files", file_count));
+    });
+
+    let mut paths = Vec::new();
+    while let Some(path) = rx.recv().await {
+        paths.push(path);
+    }
+    let _ = walker_handle.await;
+
+    Ok(paths)
+}
+
+/// Resolves a flat list of file/directory arguments (e.g. from stdin) into
+/// every regular file they contain that passes `filters`. Reports progress
+/// the same way as `collect_paths_from_directory`.
+pub async fn collect_paths_from_list(
+    files: &[&str],
+    filters: &Filters,
+    reporter: &ProgressReporter,
+    total_stages: usize,
+    verbose: bool,
+) -> Result<Vec<PathBuf>, Box<dyn std::error::Error>> {
+    let (tx, mut rx) = mpsc::channel::<PathBuf>(1000);
+    let files_owned: Vec<String> = files.iter().map(|&s| s.to_string()).collect();
+    let filters_owned = filters.clone();
+    let reporter_owned = reporter.clone();
+
+    let walker_handle = tokio::task::spawn_blocking(move || {
+        reporter_owned.start_stage("scanning", 1, total_stages, 0);
+        let mut file_count = 0usize;
+        for file_path in files_owned {
+            if file_path.is_empty() {
+                continue;
+            }
+
+            if let Ok(abs_path) = canonicalize(&file_path) {
+                if abs_path.is_dir() {
+                    if verbose {
+                        println!("Searching directory...\n\t{}", abs_path.display());
+                    }
+                    let walker = WalkDir::new(&abs_path).into_iter().filter_entry(|entry| {
+                        !entry.file_type().is_dir() || !filters_owned.excludes_dir(entry.file_name())
+                    });
+                    for entry in walker.flatten() {
+                        let file_abs_path = entry.path().to_path_buf();
+                        if file_abs_path.is_dir() || !filters_owned.accepts(&file_abs_path) {
+                            continue;
+                        }
+                        file_count += 1;
+                        reporter_owned.file_checked(0);
+                        if tx.blocking_send(file_abs_path).is_err() {
+                            return;
+                        }
+                    }
+                } else {
+                    if !filters_owned.accepts(&abs_path) {
+                        continue;
+                    }
+                    file_count += 1;
+                    reporter_owned.file_checked(0);
+                    if verbose {
+                        println!("Processing file...\n\t{}", abs_path.display());
+                    }
+                    if tx.blocking_send(abs_path).is_err() {
+                        return;
+                    }
+                }
+            }
+        }
+        debug_log(verbose, &format!("Walker finished. Found {} files", file_count));
+    });
+
+    let mut paths = Vec::new();
+    while let Some(path) = rx.recv().await {
+        paths.push(path);
+    }
+    let _ = walker_handle.await;
+
+    Ok(paths)
+}